@@ -14,10 +14,10 @@ fn main() {
             for item in hn.into_iter() {
                 println!("item: {}", item.title());
             }
-            if let Some(ref item) = hn.into_iter().nth(0) {
+            if let Some(ref item) = hn.into_iter().next() {
                 hn.hide(item);
             }
-            println!("");
+            println!();
             thread::sleep(Duration::from_millis(10000));
         }
     });
@@ -1,54 +1,116 @@
 //! hn-rs: Bindings for Hacker News (YCombinator) news feed API
 //!
 //! hn-rs is a simple binding around the firebase APIs for fetching the news
-//! feed from Hacker News.  It spawns a thread that regularly updates the top
-//! 60 items on Hacker News.
+//! feed from Hacker News.  It spawns a thread that regularly updates the
+//! tracked feeds. `Feed::Top` is tracked by default; `track_feed` adds any of
+//! `new`, `best`, `ask`, `show`, or `job` alongside it, all sharing one cache.
 //!
-//! The main class, `HackerNews`, exposes this list in the most recently sorted
-//! order as a standard Rust iterator.  The iterator returns copies of the items
-//! so the application can keep ownership if it wishes.
+//! The main class, `HackerNews`, exposes each tracked feed in the most
+//! recently sorted order as a standard Rust iterator via `iter_feed`.  The
+//! iterator returns copies of the items so the application can keep ownership
+//! if it wishes.
 //!
-//! Currently it only exposes methods to request the title and URL of news items.
+//! Beyond an item's title and URL, `Item` exposes its submitter, score,
+//! descendant count, submission time, type, and text body, and can walk its
+//! own comment subtree with `fetch_kids`/`fetch_kids_async`.
+//!
+//! `trending` surfaces terms spiking in recent story titles relative to their
+//! long-run baseline, and `add_filter` registers predicates that auto-hide
+//! freshly fetched items (see `min_score_filter`, `keyword_filter`, and
+//! `job_filter` for built-ins).
 //!
 //! News items can be marked as 'hidden' so they are not returned in future
 //! passes through the iterator.
 //!
+//! `HackerNews::with_store` persists the cache and every feed's top list,
+//! along with `seen`/`hidden` bookkeeping, to a file, so a restarted process
+//! can pick up where it left off.
+//!
 //! See the `examples/` dir for usage.
 //!
-extern crate time;
-extern crate hyper;
-extern crate hyper_tls;
-extern crate tokio_core;
-extern crate futures;
-extern crate serde_json;
-#[macro_use]
-extern crate serde_derive;
-
-use futures::future::Future;
-use futures::future::Either;
-use futures::stream::Stream;
+use serde_derive::{Serialize, Deserialize};
 
+use std::fmt;
 use std::str::FromStr;
 use std::time::Duration;
 use std::thread;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 
 use hyper::Uri;
 use hyper::client::Client;
 use hyper::client::HttpConnector;
 use hyper_tls::HttpsConnector;
 
-use tokio_core::reactor::Core;
-use tokio_core::reactor::Handle;
+const HN_URL_TOP_STORIES: &str = "https://hacker-news.firebaseio.com/v0/topstories.json";
+const HN_URL_NEW_STORIES: &str = "https://hacker-news.firebaseio.com/v0/newstories.json";
+const HN_URL_BEST_STORIES: &str = "https://hacker-news.firebaseio.com/v0/beststories.json";
+const HN_URL_ASK_STORIES: &str = "https://hacker-news.firebaseio.com/v0/askstories.json";
+const HN_URL_SHOW_STORIES: &str = "https://hacker-news.firebaseio.com/v0/showstories.json";
+const HN_URL_JOB_STORIES: &str = "https://hacker-news.firebaseio.com/v0/jobstories.json";
+const HN_URL_ITEM: &str = "https://hacker-news.firebaseio.com/v0/item/";
+const HN_URL_DISCUSSION: &str = "https://news.ycombinator.com/item?id=";
+
+/// The Firebase story list a `HackerNews` can track.
+///
+/// Each variant maps to one of Firebase's `*stories.json` endpoints. Items
+/// fetched through any feed share the same `Cache`, so switching feeds (or
+/// tracking several at once) doesn't duplicate already-cached items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum Feed {
+    #[default]
+    Top,
+    New,
+    Best,
+    Ask,
+    Show,
+    Job,
+}
+impl Feed {
+    fn url(&self) -> &'static str {
+        match *self {
+            Feed::Top => HN_URL_TOP_STORIES,
+            Feed::New => HN_URL_NEW_STORIES,
+            Feed::Best => HN_URL_BEST_STORIES,
+            Feed::Ask => HN_URL_ASK_STORIES,
+            Feed::Show => HN_URL_SHOW_STORIES,
+            Feed::Job => HN_URL_JOB_STORIES,
+        }
+    }
+}
 
-const HN_URL_TOP_STORIES: &'static str = "https://hacker-news.firebaseio.com/v0/topstories.json";
-const HN_URL_ITEM: &'static str = "https://hacker-news.firebaseio.com/v0/item/";
-const HN_URL_DISCUSSION: &'static str = "https://news.ycombinator.com/item?id=";
+const FETCH_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// Error fetching data from the Firebase HN API.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The underlying HTTP request failed.
+    Http(hyper::Error),
+    /// The request didn't complete within `FETCH_TIMEOUT`.
+    Timeout,
+}
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::Http(e) => write!(f, "HTTP error: {}", e),
+            FetchError::Timeout => write!(f, "timed out"),
+        }
+    }
+}
+impl std::error::Error for FetchError {}
+impl From<hyper::Error> for FetchError {
+    fn from(e: hyper::Error) -> Self {
+        FetchError::Http(e)
+    }
+}
 
 /// Stores the metadata about a single news item
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Item {
     by: String,
     descendants: Option<u64>,
@@ -58,7 +120,7 @@ pub struct Item {
     time: u64,
     title: Option<String>,
     text: Option<String>,
-    #[serde(rename(deserialize = "type"))]
+    #[serde(rename = "type")]
     item_type: String,
     url: Option<String>,
 
@@ -69,6 +131,10 @@ pub struct Item {
     seen: bool,
     #[serde(default)]
     hidden: bool,
+    #[serde(default)]
+    filtered: bool,
+    #[serde(skip)]
+    source: Option<ItemSource>,
 }
 
 impl Item {
@@ -86,6 +152,112 @@ impl Item {
             None => format!("{}{}", HN_URL_DISCUSSION, self.id),
         }
     }
+    /// Return the URL of the HN discussion thread for this item, regardless
+    /// of whether it links out to an external site.
+    pub fn discussion_url(&self) -> String {
+        format!("{}{}", HN_URL_DISCUSSION, self.id)
+    }
+    /// Return the username of the item's submitter.
+    pub fn by(&self) -> &str {
+        &self.by
+    }
+    /// Return the item's score, if any.
+    pub fn score(&self) -> Option<u32> {
+        self.score
+    }
+    /// Return the number of descendant comments, if any.
+    pub fn descendants(&self) -> Option<u64> {
+        self.descendants
+    }
+    /// Return the item's submission time, as a Unix timestamp.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+    /// Return the ids of this item's direct child comments, if any.
+    pub fn kids(&self) -> Option<&[u64]> {
+        self.kids.as_deref()
+    }
+    /// Return the item's text body (for comments and `Ask HN`/`text` posts).
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+    /// Return the HN item type, e.g. `"story"`, `"comment"`, or `"job"`.
+    pub fn item_type(&self) -> &str {
+        &self.item_type
+    }
+    /// Return whether this item was hidden by a registered filter predicate,
+    /// as opposed to a manual call to `HackerNews::hide`.
+    pub fn filtered(&self) -> bool {
+        self.filtered
+    }
+    /// Recursively fetch this item's comment subtree.
+    ///
+    /// Falls back to an empty list if the item wasn't fetched through a
+    /// `HackerNews` (e.g. it was constructed directly from JSON in a test).
+    /// Safe to call on or off a `tokio` runtime of any flavor; prefer
+    /// `fetch_kids_async` instead if already on one, to avoid blocking a
+    /// worker thread.
+    pub fn fetch_kids(&self) -> Vec<Item> {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(self.fetch_kids_async()))
+            }
+            _ => std::thread::scope(|s| {
+                s.spawn(|| {
+                    let rt = tokio::runtime::Runtime::new().unwrap();
+                    rt.block_on(self.fetch_kids_async())
+                })
+                .join()
+                .unwrap()
+            }),
+        }
+    }
+    /// Async equivalent of `fetch_kids`, for callers already on a `tokio` runtime.
+    pub async fn fetch_kids_async(&self) -> Vec<Item> {
+        self.fetch_kids_boxed().await
+    }
+    fn fetch_kids_boxed<'a>(&'a self) -> std::pin::Pin<Box<dyn std::future::Future<Output = Vec<Item>> + Send + 'a>> {
+        Box::pin(async move {
+            let source = match self.source {
+                Some(ref source) => source.clone(),
+                None => return Vec::new(),
+            };
+            let kid_ids = self.kids.clone().unwrap_or_default();
+            let mut out = Vec::new();
+            for id in kid_ids {
+                if let Some(item) = source.fetch_item(id).await {
+                    let grandkids = item.fetch_kids_boxed().await;
+                    out.push(item);
+                    out.extend(grandkids);
+                }
+            }
+            out
+        })
+    }
+}
+
+/// Shared handle used by an `Item` to fetch its own comment subtree through
+/// the same HTTP client and cache as the `HackerNews` that produced it.
+#[derive(Clone)]
+struct ItemSource {
+    client: Client<HttpsConnector<HttpConnector>>,
+    cache: Cache,
+}
+impl fmt::Debug for ItemSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ItemSource").finish()
+    }
+}
+impl ItemSource {
+    async fn fetch_item(&self, id: u64) -> Option<Item> {
+        if let Some(item) = self.cache.read().unwrap().get(&id).cloned() {
+            return Some(item);
+        }
+        let mut item = HackerNews::fetch_item_raw(&self.client, id).await?;
+        item.source = Some(self.clone());
+        self.cache.write().unwrap().insert(id, item.clone());
+        Some(item)
+    }
 }
 
 #[doc(hidden)]
@@ -95,7 +267,7 @@ pub struct Cache {
 }
 impl std::ops::Deref for Cache {
     type Target = RwLock<BTreeMap<u64, Item>>;
-    fn deref(&self) -> &Self::Target { &*self.x }
+    fn deref(&self) -> &Self::Target { &self.x }
 }
 
 #[doc(hidden)]
@@ -105,21 +277,151 @@ pub struct TopList {
 }
 impl std::ops::Deref for TopList {
     type Target = RwLock<Vec<u64>>;
-    fn deref(&self) -> &Self::Target { &*self.x }
+    fn deref(&self) -> &Self::Target { &self.x }
+}
+
+/// How far back `trending()` looks when counting recent occurrences of a term.
+const TREND_WINDOW_SECS: u64 = 6 * 60 * 60;
+
+/// Common words excluded from trending-term tracking.
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "that", "with", "this", "from", "your", "have",
+    "are", "was", "not", "but", "you", "can", "has", "will", "how", "what",
+    "new", "all", "its", "out", "use", "now", "why", "who", "get", "one",
+    "about", "into", "than", "then", "when", "where", "they", "them",
+];
+
+/// Tokenize a title into the lowercased terms tracked for trending, dropping
+/// stopwords and anything shorter than 3 characters.
+fn title_terms(title: &str) -> Vec<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
 }
 
 #[doc(hidden)]
 #[derive(Default)]
+pub struct TermTrend {
+    /// Timestamps of occurrences within the last `TREND_WINDOW_SECS`.
+    window: VecDeque<u64>,
+    /// Occurrences ever seen for this term, used to derive a baseline rate.
+    total: u64,
+    /// When this term was first observed.
+    first_seen: u64,
+}
+
+/// Evict timestamps older than `TREND_WINDOW_SECS` from `trend`'s window as
+/// of `now`, then score it as current-window occurrences divided by its
+/// long-run average occurrences per window, so a term spiking above its
+/// usual baseline scores above one that's merely frequent.
+fn trend_score(trend: &mut TermTrend, now: u64) -> f64 {
+    // Timestamps are pushed in fetch order, not time order, so a fresh one
+    // at the front doesn't mean everything behind it is fresh too.
+    trend.window.retain(|&t| now.saturating_sub(t) <= TREND_WINDOW_SECS);
+    let current = trend.window.len() as f64;
+    let span_secs = now.saturating_sub(trend.first_seen).max(1);
+    let windows = (span_secs as f64 / TREND_WINDOW_SECS as f64).max(1.0);
+    let baseline = (trend.total as f64 / windows).max(0.01);
+    current / baseline
+}
+
+#[doc(hidden)]
+#[derive(Clone,Default)]
+pub struct Trends {
+    x: Arc<RwLock<HashMap<String, TermTrend>>>,
+}
+impl std::ops::Deref for Trends {
+    type Target = RwLock<HashMap<String, TermTrend>>;
+    fn deref(&self) -> &Self::Target { &self.x }
+}
+impl Trends {
+    /// Record one occurrence of every surviving term in `title` at `time`.
+    fn record(&self, title: &str, time: u64) {
+        let mut writer = self.write().unwrap();
+        for term in title_terms(title) {
+            let trend = writer.entry(term).or_insert_with(|| TermTrend {
+                window: VecDeque::new(),
+                total: 0,
+                first_seen: time,
+            });
+            trend.total += 1;
+            trend.window.push_back(time);
+        }
+    }
+}
+
+/// A predicate used to auto-hide items, registered via `HackerNews::add_filter`.
+type FilterFn = Box<dyn Fn(&Item) -> bool + Send + Sync>;
+
+#[doc(hidden)]
+#[derive(Clone,Default)]
+pub struct Filters {
+    x: Arc<RwLock<Vec<FilterFn>>>,
+}
+impl std::ops::Deref for Filters {
+    type Target = RwLock<Vec<FilterFn>>;
+    fn deref(&self) -> &Self::Target { &self.x }
+}
+impl Filters {
+    /// Run every registered predicate against `item`, hiding it (and
+    /// tagging it as filtered, rather than manually hidden) on a match.
+    fn apply(&self, item: &mut Item) {
+        for filter in self.read().unwrap().iter() {
+            if filter(item) {
+                item.hidden = true;
+                item.filtered = true;
+                break;
+            }
+        }
+    }
+}
+
+/// Built-in filter: hide items scoring below `min`.
+pub fn min_score_filter(min: u32) -> impl Fn(&Item) -> bool + Send + Sync + Clone {
+    move |item: &Item| item.score.unwrap_or(0) < min
+}
+
+/// Built-in filter: hide items whose title matches the regex `pattern`.
+pub fn keyword_filter(pattern: &str) -> Result<impl Fn(&Item) -> bool + Send + Sync + Clone, regex::Error> {
+    let re = regex::Regex::new(pattern)?;
+    Ok(move |item: &Item| re.is_match(&item.title()))
+}
+
+/// Built-in filter: hide job postings.
+pub fn job_filter(item: &Item) -> bool {
+    item.item_type() == "job"
+}
+
+#[doc(hidden)]
 pub struct IHackerNews {
-    pub top: TopList,
+    pub tops: RwLock<HashMap<Feed, TopList>>,
     pub cache: Cache,
+    pub trends: Trends,
+    pub filters: Filters,
+    shutdown: AtomicBool,
+    thread: std::sync::Mutex<Option<thread::JoinHandle<()>>>,
+    primary_feed: Feed,
+    store: Option<PathBuf>,
+}
+
+/// How often the fetch loop persists `store` to disk, so `hide()` and newly
+/// cached items survive a restart without writing on every 100ms tick.
+const STORE_INTERVAL_SECS: i64 = 30;
+
+/// On-disk representation of a `HackerNews`' persisted state.
+#[derive(Serialize, Deserialize, Default)]
+struct PersistedState {
+    tops: HashMap<Feed, Vec<u64>>,
+    items: BTreeMap<u64, Item>,
 }
 
 /// Main interface to the Hacker News API
 ///
 /// # Examples
 ///
-/// ```rust
+/// ```rust,no_run
 /// extern crate hn;
 /// use std::time::Duration;
 /// use std::thread;
@@ -135,22 +437,37 @@ pub struct IHackerNews {
 /// }
 /// ```
 ///
-#[derive(Clone,Default)]
+#[derive(Clone)]
 pub struct HackerNews {
     x: Arc<IHackerNews>,
 }
 impl std::ops::Deref for HackerNews {
     type Target = IHackerNews;
-    fn deref(&self) -> &Self::Target { &*self.x }
+    fn deref(&self) -> &Self::Target { &self.x }
+}
+impl Drop for IHackerNews {
+    /// Last-clone shutdown: stops the background thread and saves the store,
+    /// skipping the join if this runs on `hn_thread` itself (a self-join
+    /// would panic). See commit history for why each case is handled here.
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(ref path) = self.store {
+            HackerNews::save_store(path, &self.tops, &self.cache);
+        }
+        let mut guard = self.thread.lock().unwrap();
+        if let Some(handle) = guard.take() {
+            if handle.thread().id() == thread::current().id() {
+                return;
+            }
+            let _ = handle.join();
+        }
+    }
 }
 impl<'a> IntoIterator for &'a HackerNews {
     type Item = Item;
     type IntoIter = HackerNewsIterator<'a>;
     fn into_iter(self) -> Self::IntoIter {
-        HackerNewsIterator {
-            hn: self,
-            idx: 0,
-        }
+        self.iter_feed(self.primary_feed)
     }
 }
 
@@ -160,12 +477,21 @@ impl<'a> IntoIterator for &'a HackerNews {
 /// the time of the last update.
 pub struct HackerNewsIterator<'a> {
     hn: &'a HackerNews,
+    feed: Feed,
     idx: usize,
 }
 impl<'a> Iterator for HackerNewsIterator<'a> {
     type Item = Item;
     fn next(&mut self) -> Option<Item> {
-        let reader = self.hn.top.read().unwrap();
+        let top = self.hn.tops.read().unwrap().get(&self.feed).cloned();
+        let top = match top {
+            Some(top) => top,
+            None => {
+                self.idx = 0;
+                return None;
+            }
+        };
+        let reader = top.read().unwrap();
         while self.idx < reader.len() {
             let item: Option<&u64> = (*reader).get(self.idx);
             if let Some(item) = item {
@@ -175,27 +501,114 @@ impl<'a> Iterator for HackerNewsIterator<'a> {
                         item.seen = true;
                         return Some((*item).clone());
                     }
+                    continue;
                 }
             }
+            // Id is in the top list but not (yet, or ever — HN returns null
+            // for flagged/dead/deleted items, which never makes it into the
+            // cache) in the cache. Skip past it rather than re-checking the
+            // same id forever.
+            self.idx += 1;
         }
         self.idx = 0;
         None
     }
 }
 
+impl Default for HackerNews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl HackerNews {
     /// Return a newly allocated HN wrapper, and spawn background thread
+    ///
+    /// Tracks the `Feed::Top` list, matching the crate's original behavior.
     pub fn new() -> HackerNews {
-        let hn: HackerNews = Default::default();
-        let thread_hn = hn.clone();
-        let _ = thread::spawn(move || {
-            HackerNews::hn_thread(thread_hn);
+        HackerNews::new_internal(Feed::Top, None)
+    }
+    /// Return a newly allocated HN wrapper tracking `feed`, and spawn the
+    /// background thread that keeps it refreshed.
+    pub fn new_with_feed(feed: Feed) -> HackerNews {
+        HackerNews::new_internal(feed, None)
+    }
+    /// Return a newly allocated HN wrapper that persists its cache, top
+    /// list, and `seen`/`hidden` bookkeeping to `path`.
+    ///
+    /// If `path` already holds previously persisted state, it's loaded
+    /// before the background thread starts, so a restarted process picks
+    /// up right where it left off (in particular, items hidden in a prior
+    /// run stay hidden). State is then written back to `path` periodically
+    /// from the fetch loop, and once more on shutdown.
+    pub fn with_store<P: Into<PathBuf>>(path: P) -> HackerNews {
+        HackerNews::new_internal(Feed::Top, Some(path.into()))
+    }
+    fn new_internal(feed: Feed, store: Option<PathBuf>) -> HackerNews {
+        let mut tops = HashMap::new();
+        tops.insert(feed, TopList::default());
+        let cache = Cache::default();
+        if let Some(ref path) = store {
+            if let Some(state) = HackerNews::load_store(path) {
+                for (loaded_feed, ids) in state.tops {
+                    *tops.entry(loaded_feed).or_default().write().unwrap() = ids;
+                }
+                *cache.write().unwrap() = state.items;
+            }
+        }
+        let inner = IHackerNews {
+            tops: RwLock::new(tops),
+            cache,
+            trends: Trends::default(),
+            filters: Filters::default(),
+            shutdown: AtomicBool::new(false),
+            thread: std::sync::Mutex::new(None),
+            primary_feed: feed,
+            store,
+        };
+        let hn = HackerNews { x: Arc::new(inner) };
+        let weak = Arc::downgrade(&hn.x);
+        let handle = thread::spawn(move || {
+            HackerNews::hn_thread(weak);
         });
+        *hn.thread.lock().unwrap() = Some(handle);
         hn
     }
-    /// Return number of items currently in the 'top list'
+    /// Start tracking an additional feed alongside the ones already tracked.
+    ///
+    /// The background thread picks up newly tracked feeds on its next
+    /// refresh pass; items fetched through any feed share the same cache.
+    pub fn track_feed(&self, feed: Feed) {
+        self.tops.write().unwrap().entry(feed).or_default();
+    }
+    /// Return an iterator over a specific tracked feed.
+    ///
+    /// `into_iter()` iterates the feed the `HackerNews` was constructed
+    /// with; use this to iterate any other feed passed to `track_feed`.
+    pub fn iter_feed<'a>(&'a self, feed: Feed) -> HackerNewsIterator<'a> {
+        HackerNewsIterator {
+            hn: self,
+            feed,
+            idx: 0,
+        }
+    }
+    /// Signal the background thread to stop fetching.
+    ///
+    /// The thread is joined when the last clone of this `HackerNews` is
+    /// dropped; call this explicitly if you want to stop polling sooner
+    /// without waiting for every clone to go out of scope.
+    pub fn shutdown(&self) {
+        self.x.shutdown.store(true, Ordering::Relaxed);
+    }
+    /// Return number of items currently in the primary feed's 'top list'
     pub fn len(&self) -> usize {
-        self.top.read().unwrap().len()
+        match self.tops.read().unwrap().get(&self.primary_feed) {
+            Some(top) => top.read().unwrap().len(),
+            None => 0,
+        }
+    }
+    /// Return whether the primary feed's 'top list' is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
     /// Hide an item so it isn't returned in future iterator passes
     pub fn hide(&self, item: &Item) {
@@ -204,49 +617,137 @@ impl HackerNews {
             item.hidden = true;
         }
     }
-    fn hn_thread(hn: HackerNews) {
-        let mut core = Core::new().unwrap();
-        let handle = core.handle();
-        let https = HttpsConnector::new(4, &handle).unwrap();
-        let client = Client::configure()
-            .keep_alive(true)
-            .connector(https)
-            .build(&handle);
-        let mut last_update_time = 0;
-        loop {
-            let now = time::now_utc().to_timespec().sec as i64;
-            if now > last_update_time + 10 {
-                if HackerNews::update_top_stories(&mut core, &client, &hn.top).is_ok() {
-                    HackerNews::update_item_cache(&client, &handle, &hn.top, &hn.cache);
+    /// Register a predicate that auto-hides freshly fetched items it matches.
+    ///
+    /// Predicates run once on each freshly fetched item, just before it's
+    /// inserted into the cache; they don't get a second pass at items
+    /// already cached. See
+    /// `min_score_filter`, `keyword_filter`, and `job_filter` for built-in
+    /// predicates.
+    pub fn add_filter<F>(&self, filter: F)
+    where
+        F: Fn(&Item) -> bool + Send + Sync + 'static,
+    {
+        self.filters.write().unwrap().push(Box::new(filter));
+    }
+    /// Return the top `n` trending terms extracted from story titles.
+    ///
+    /// Each term's score is its occurrence count in the last
+    /// `TREND_WINDOW_SECS` divided by its long-run average count per window,
+    /// so a term spiking above its usual baseline ranks above one that's
+    /// merely frequent. Eviction of stale timestamps and score computation
+    /// both happen here, lazily, so the fetch loop itself stays cheap.
+    pub fn trending(&self, n: usize) -> Vec<(String, f64)> {
+        let now = time::now_utc().to_timespec().sec as u64;
+        let mut writer = self.trends.write().unwrap();
+        let mut scored: Vec<(String, f64)> = writer.iter_mut()
+            .map(|(term, trend)| (term.clone(), trend_score(trend, now)))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(n);
+        scored
+    }
+    /// Background fetch loop, run on its own dedicated `tokio` runtime.
+    ///
+    /// Kept on a plain OS thread (rather than asking the caller to supply a
+    /// runtime) so `HackerNews::new()` stays a zero-setup constructor. Holds
+    /// only a `Weak` reference to the shared state so the background thread
+    /// doesn't itself keep `HackerNews` alive; `Drop` relies on the strong
+    /// count reaching zero to know it's the last handle.
+    fn hn_thread(hn: std::sync::Weak<IHackerNews>) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let https = HttpsConnector::new();
+            let client: Client<_> = Client::builder().build(https);
+            if let Some(hn) = hn.upgrade() {
+                // Items loaded from a persisted store deserialize `source` as
+                // `None` (it's `#[serde(skip)]`), since the client/cache handle
+                // can't be serialized. Without re-stamping it here, every item
+                // that survived a restart would have its `fetch_kids` silently
+                // and permanently return an empty `Vec`.
+                let source = ItemSource { client: client.clone(), cache: hn.cache.clone() };
+                for item in hn.cache.write().unwrap().values_mut() {
+                    if item.source.is_none() {
+                        item.source = Some(source.clone());
+                    }
                 }
-                last_update_time = now;
             }
-            core.turn(Some(Duration::from_millis(100)));
-        }
-    }
-    fn update_top_stories(core: &mut Core,
-                          client: &Client<HttpsConnector<HttpConnector>>,
-                          top: &RwLock<Vec<u64>>) -> Result<(), hyper::error::Error> {
-        let handle = core.handle();
-        let uri = Uri::from_str(HN_URL_TOP_STORIES).ok().unwrap();
-        let request = client.get(uri).and_then(|res| {
-            res.body().concat2()
-        });
-
-        let timeout = tokio_core::reactor::Timeout::new(Duration::from_millis(5000), &handle).unwrap();
-        let timed_request = request.select2(timeout).then(|res| match res {
-            Ok(Either::A((data, _timeout))) => Ok(data),
-            Ok(Either::B((_timeout_error, _get))) => {
-                Err(hyper::Error::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Timed out requesting top list",
-                )))
+            let mut last_update_time = 0;
+            let mut last_store_time = 0;
+            let mut ticker = tokio::time::interval(Duration::from_millis(100));
+            loop {
+                ticker.tick().await;
+                let hn = match hn.upgrade() {
+                    Some(hn) => hn,
+                    None => break,
+                };
+                if hn.shutdown.load(Ordering::Relaxed) {
+                    if let Some(ref path) = hn.store {
+                        HackerNews::save_store(path, &hn.tops, &hn.cache);
+                    }
+                    break;
+                }
+                let now = time::now_utc().to_timespec().sec as i64;
+                if now > last_update_time + 10 {
+                    let feeds: Vec<Feed> = hn.tops.read().unwrap().keys().cloned().collect();
+                    for feed in feeds {
+                        let top = hn.tops.read().unwrap().get(&feed).cloned();
+                        let top = match top {
+                            Some(top) => top,
+                            None => continue,
+                        };
+                        if HackerNews::update_top_stories(&client, feed, &top).await.is_ok() {
+                            HackerNews::update_item_cache(&client, &top, &hn.cache, &hn.trends, &hn.filters).await;
+                        }
+                    }
+                    last_update_time = now;
+                }
+                if let Some(ref path) = hn.store {
+                    if now > last_store_time + STORE_INTERVAL_SECS {
+                        HackerNews::save_store(path, &hn.tops, &hn.cache);
+                        last_store_time = now;
+                    }
+                }
             }
-            Err(Either::A((error, _timeout))) => Err(error),
-            Err(Either::B((timeout_error, _get))) => Err(From::from(timeout_error)),
         });
-
-        let got = core.run(timed_request)?;
+    }
+    fn load_store(path: &Path) -> Option<PersistedState> {
+        let data = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+    /// Write `PersistedState` to `path`, atomically.
+    ///
+    /// Writes to a sibling temp file and renames it into place so a save is
+    /// all-or-nothing: this runs unsupervised from the background thread
+    /// every `STORE_INTERVAL_SECS` and again on shutdown, and a crash or
+    /// kill mid-write to `path` directly would otherwise leave a
+    /// truncated/corrupt file that `load_store` can only silently discard.
+    fn save_store(path: &Path, tops: &RwLock<HashMap<Feed, TopList>>, cache: &Cache) {
+        let tops = tops.read().unwrap().iter()
+            .map(|(feed, top)| (*feed, top.read().unwrap().clone()))
+            .collect();
+        let items = cache.read().unwrap().clone();
+        if let Ok(json) = serde_json::to_string(&PersistedState { tops, items }) {
+            let mut tmp_path = path.as_os_str().to_owned();
+            tmp_path.push(".tmp");
+            let tmp_path = PathBuf::from(tmp_path);
+            if std::fs::write(&tmp_path, json).is_ok() {
+                let _ = std::fs::rename(&tmp_path, path);
+            }
+        }
+    }
+    async fn update_top_stories(client: &Client<HttpsConnector<HttpConnector>>,
+                                 feed: Feed,
+                                 top: &RwLock<Vec<u64>>) -> Result<(), FetchError> {
+        let uri = Uri::from_str(feed.url()).ok().unwrap();
+        let request = async {
+            let res = client.get(uri).await?;
+            hyper::body::to_bytes(res.into_body()).await
+        };
+        let got = match tokio::time::timeout(FETCH_TIMEOUT, request).await {
+            Ok(res) => res?,
+            Err(_) => return Err(FetchError::Timeout),
+        };
         let top_stories_str = std::str::from_utf8(&got).unwrap();
         {
             let mut writer = top.write().unwrap();
@@ -258,47 +759,54 @@ impl HackerNews {
         }
         Ok(())
     }
-    fn update_item_cache(client: &Client<HttpsConnector<HttpConnector>>,
-                         handle: &Handle,
-                         top: &RwLock<Vec<u64>>,
-                         cache: &Cache) {
+    async fn update_item_cache(client: &Client<HttpsConnector<HttpConnector>>,
+                                top: &RwLock<Vec<u64>>,
+                                cache: &Cache,
+                                trends: &Trends,
+                                filters: &Filters) {
         let stories = top.read().unwrap();
         let stories: Vec<&u64>  = stories.iter().filter(|s| {
             let reader = cache.read().unwrap();
             !(*reader).contains_key(*s)
         }).collect();
-        let mut req_count = 0;
-        for story in stories {
-            if req_count >= 60 {
-                // Max 60 per batch
-                break;
-            }
-            let uri = format!("{}{}.json", HN_URL_ITEM, story);
-            let id = story.clone();
-            let uri = Uri::from_str(&uri).ok().unwrap();
+        // Max 60 per batch
+        for story in stories.into_iter().take(60) {
+            let id = *story;
             let future_cache = cache.clone();
-            let req = client.get(uri).and_then(|res| {
-                res.body().concat2()
-            }).then(move |body| {
-                if body.is_err() {
-                    return Err(());
-                }
-                let body = body.unwrap();
-                let item_str = std::str::from_utf8(&body).unwrap();
-                let item: Result<Item,_> = serde_json::from_str(item_str);
-                if let Ok(item) = item {
+            let future_trends = trends.clone();
+            let future_filters = filters.clone();
+            let client = client.clone();
+            tokio::spawn(async move {
+                if let Some(mut item) = HackerNews::fetch_item_raw(&client, id).await {
+                    item.source = Some(ItemSource {
+                        client: client.clone(),
+                        cache: future_cache.clone(),
+                    });
+                    if let Some(ref title) = item.title {
+                        future_trends.record(title, item.time);
+                    }
+                    future_filters.apply(&mut item);
                     let mut writer = future_cache.write().unwrap();
                     (*writer).insert(id, item);
                 }
-                Ok(())
             });
-
-            let timeout = tokio_core::reactor::Timeout::new(Duration::from_millis(5000), &handle).unwrap();
-            let timed_request = req.select2(timeout).then(|_| { Ok(()) });
-            handle.spawn(timed_request);
-            req_count += 1;
         }
     }
+    /// Fetch and parse a single item by id, without touching the cache.
+    async fn fetch_item_raw(client: &Client<HttpsConnector<HttpConnector>>, id: u64) -> Option<Item> {
+        let uri = format!("{}{}.json", HN_URL_ITEM, id);
+        let uri = Uri::from_str(&uri).ok()?;
+        let request = async {
+            let res = client.get(uri).await.ok()?;
+            hyper::body::to_bytes(res.into_body()).await.ok()
+        };
+        let body = match tokio::time::timeout(FETCH_TIMEOUT, request).await {
+            Ok(Some(body)) => body,
+            _ => return None,
+        };
+        let item_str = std::str::from_utf8(&body).ok()?;
+        serde_json::from_str(item_str).ok()
+    }
 }
 
 #[cfg(test)]
@@ -311,4 +819,337 @@ mod tests {
         let _ = HackerNews::new();
         thread::sleep(Duration::from_millis(300));
     }
+
+    #[test]
+    fn title_terms_splits_on_punctuation_and_lowercases() {
+        use super::title_terms;
+        assert_eq!(title_terms("Show HN: Rust-based Parser!"), vec!["show", "rust", "based", "parser"]);
+    }
+
+    #[test]
+    fn title_terms_drops_stopwords() {
+        use super::title_terms;
+        assert_eq!(title_terms("this new for that"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn title_terms_drops_tokens_shorter_than_three_chars() {
+        use super::title_terms;
+        assert_eq!(title_terms("is a go to it"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn trend_score_spike_above_baseline_scores_high() {
+        use super::{trend_score, TermTrend, TREND_WINDOW_SECS};
+        use std::collections::VecDeque;
+        // Seen once per window on average over 10 windows, then spikes to 5
+        // occurrences within the current window: should score well above 1.0.
+        let now = 10 * TREND_WINDOW_SECS;
+        let mut trend = TermTrend {
+            window: VecDeque::from(vec![now - 500, now - 400, now - 300, now - 200, now - 100]),
+            total: 14,
+            first_seen: 0,
+        };
+        let score = trend_score(&mut trend, now);
+        assert!(score > 1.0, "expected spike to score above baseline, got {}", score);
+    }
+
+    #[test]
+    fn trend_score_evicts_stale_window_entries() {
+        use super::{trend_score, TermTrend, TREND_WINDOW_SECS};
+        use std::collections::VecDeque;
+        let mut trend = TermTrend {
+            window: VecDeque::from(vec![0, TREND_WINDOW_SECS * 5]),
+            total: 2,
+            first_seen: 0,
+        };
+        trend_score(&mut trend, TREND_WINDOW_SECS * 5);
+        assert_eq!(trend.window.len(), 1, "entry older than the window should be evicted");
+    }
+
+    #[test]
+    fn trend_score_never_seen_in_window_scores_zero() {
+        use super::{trend_score, TermTrend};
+        use std::collections::VecDeque;
+        let mut trend = TermTrend {
+            window: VecDeque::new(),
+            total: 5,
+            first_seen: 0,
+        };
+        assert_eq!(trend_score(&mut trend, 1_000_000), 0.0);
+    }
+
+    fn item(json: &str) -> super::Item {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Build a `HackerNews` directly around `tops`/`cache`, bypassing
+    /// `HackerNews::new` so tests can exercise `iter_feed`/`track_feed`
+    /// without a live background thread.
+    fn test_hn(tops: std::collections::HashMap<super::Feed, super::TopList>, cache: super::Cache) -> super::HackerNews {
+        use super::{Feed, Filters, HackerNews, IHackerNews, Trends};
+        use std::sync::atomic::AtomicBool;
+        use std::sync::{Arc, Mutex, RwLock};
+
+        HackerNews {
+            x: Arc::new(IHackerNews {
+                tops: RwLock::new(tops),
+                cache,
+                trends: Trends::default(),
+                filters: Filters::default(),
+                shutdown: AtomicBool::new(true),
+                thread: Mutex::new(None),
+                primary_feed: Feed::Top,
+                store: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn track_feed_keeps_feeds_independent_over_a_shared_cache() {
+        use super::{Cache, Feed, TopList};
+        use std::collections::HashMap;
+
+        let cache = Cache::default();
+        cache.write().unwrap().insert(1, item(r#"{"by":"a","id":1,"time":0,"type":"story","title":"top story"}"#));
+        cache.write().unwrap().insert(2, item(r#"{"by":"a","id":2,"time":0,"type":"story","title":"new story"}"#));
+
+        let mut tops = HashMap::new();
+        tops.insert(Feed::Top, TopList::default());
+        tops.get(&Feed::Top).unwrap().write().unwrap().push(1);
+
+        let hn = test_hn(tops, cache);
+
+        // Feed::New isn't tracked yet, so it has no top list to iterate.
+        assert_eq!(hn.iter_feed(Feed::New).count(), 0);
+
+        hn.track_feed(Feed::New);
+        hn.tops.write().unwrap().get(&Feed::New).unwrap().write().unwrap().push(2);
+
+        let top_titles: Vec<_> = hn.iter_feed(Feed::Top).map(|i| i.title()).collect();
+        let new_titles: Vec<_> = hn.iter_feed(Feed::New).map(|i| i.title()).collect();
+        assert_eq!(top_titles, vec!["top story".to_string()]);
+        assert_eq!(new_titles, vec!["new story".to_string()]);
+    }
+
+    #[test]
+    fn iter_feed_skips_ids_missing_from_cache() {
+        use super::{Cache, Feed, TopList};
+        use std::collections::HashMap;
+
+        // Id 1 is in the top list but was never cached (e.g. HN returned
+        // null for a flagged/dead/deleted item). Before the fix, `next()`
+        // never advanced past a cache miss and spun on it forever.
+        let cache = Cache::default();
+        cache.write().unwrap().insert(2, item(r#"{"by":"a","id":2,"time":0,"type":"story","title":"surviving story"}"#));
+
+        let mut tops = HashMap::new();
+        tops.insert(Feed::Top, TopList::default());
+        tops.get(&Feed::Top).unwrap().write().unwrap().extend([1, 2]);
+
+        let hn = test_hn(tops, cache);
+
+        let titles: Vec<_> = hn.iter_feed(Feed::Top).map(|i| i.title()).collect();
+        assert_eq!(titles, vec!["surviving story".to_string()]);
+    }
+
+    #[test]
+    fn min_score_filter_hides_below_threshold_only() {
+        use super::min_score_filter;
+        let filter = min_score_filter(50);
+        let low = item(r#"{"by":"a","id":1,"time":0,"type":"story","score":10}"#);
+        let high = item(r#"{"by":"a","id":2,"time":0,"type":"story","score":50}"#);
+        let missing = item(r#"{"by":"a","id":3,"time":0,"type":"story"}"#);
+        assert!(filter(&low));
+        assert!(!filter(&high), "score equal to the threshold should not be hidden");
+        assert!(filter(&missing), "items with no score should be treated as 0");
+    }
+
+    #[test]
+    fn keyword_filter_matches_title_case_insensitively() {
+        use super::keyword_filter;
+        let filter = keyword_filter(r"(?i)rust").unwrap();
+        let matching = item(r#"{"by":"a","id":1,"time":0,"type":"story","title":"Rewritten in Rust"}"#);
+        let other = item(r#"{"by":"a","id":2,"time":0,"type":"story","title":"A Go story"}"#);
+        assert!(filter(&matching));
+        assert!(!filter(&other));
+    }
+
+    #[test]
+    fn keyword_filter_rejects_invalid_pattern() {
+        use super::keyword_filter;
+        assert!(keyword_filter("(unterminated").is_err());
+    }
+
+    #[test]
+    fn job_filter_matches_only_job_type() {
+        use super::job_filter;
+        let job = item(r#"{"by":"a","id":1,"time":0,"type":"job"}"#);
+        let story = item(r#"{"by":"a","id":2,"time":0,"type":"story"}"#);
+        assert!(job_filter(&job));
+        assert!(!job_filter(&story));
+    }
+
+    /// Build a parent `Item` wired to an `ItemSource` whose `Cache` already
+    /// holds its one kid, so `fetch_kids`/`fetch_kids_async` resolve it
+    /// without ever touching the network.
+    fn item_with_cached_kid() -> super::Item {
+        use super::{Cache, ItemSource};
+        use hyper::client::Client;
+        use hyper_tls::HttpsConnector;
+
+        let cache = Cache::default();
+        let kid = item(r#"{"by":"a","id":2,"time":0,"type":"comment","text":"a reply"}"#);
+        cache.write().unwrap().insert(2, kid);
+
+        let client: Client<_> = Client::builder().build(HttpsConnector::new());
+        let source = ItemSource { client, cache };
+        let mut parent = item(r#"{"by":"a","id":1,"time":0,"type":"story","title":"top story"}"#);
+        parent.kids = Some(vec![2]);
+        parent.source = Some(source);
+        parent
+    }
+
+    #[tokio::test]
+    async fn fetch_kids_async_returns_cached_kids_without_network() {
+        let kids = item_with_cached_kid().fetch_kids_async().await;
+        assert_eq!(kids.len(), 1);
+        assert_eq!(kids[0].text(), Some("a reply"));
+    }
+
+    // Default #[tokio::test] flavor is current_thread, the case that used to
+    // panic in `block_in_place` before fetch_kids learned to fall back to a
+    // scoped OS thread for it.
+    #[tokio::test]
+    async fn fetch_kids_resolves_cached_kids_on_a_current_thread_runtime() {
+        let kids = item_with_cached_kid().fetch_kids();
+        assert_eq!(kids.len(), 1);
+        assert_eq!(kids[0].text(), Some("a reply"));
+    }
+
+    #[test]
+    fn fetch_kids_resolves_cached_kids_with_no_runtime_current() {
+        let kids = item_with_cached_kid().fetch_kids();
+        assert_eq!(kids.len(), 1);
+        assert_eq!(kids[0].text(), Some("a reply"));
+    }
+
+    // A multi_thread runtime (the flavor this crate's own fetch loop runs
+    // on) is current, so fetch_kids drives it via block_in_place rather
+    // than either fallback. Covered separately from the current_thread and
+    // no-runtime cases above, which can't reach it.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn fetch_kids_resolves_cached_kids_on_a_multi_thread_runtime() {
+        let kids = item_with_cached_kid().fetch_kids();
+        assert_eq!(kids.len(), 1);
+        assert_eq!(kids[0].text(), Some("a reply"));
+    }
+
+    fn store_path(name: &str) -> std::path::PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("hn-rs-test-{}-{}.json", name, std::process::id()));
+        path
+    }
+
+    #[test]
+    fn save_and_load_store_round_trips_state() {
+        use super::{HackerNews, Feed, TopList, Cache};
+        use std::collections::HashMap;
+        use std::sync::RwLock;
+
+        let path = store_path("round-trip");
+        let _ = std::fs::remove_file(&path);
+
+        let mut tops = HashMap::new();
+        tops.insert(Feed::Top, TopList::default());
+        tops.get(&Feed::Top).unwrap().write().unwrap().extend([1, 2, 3]);
+        let tops = RwLock::new(tops);
+
+        let cache = Cache::default();
+        let mut hidden = item(r#"{"by":"a","id":1,"time":0,"type":"story","title":"x"}"#);
+        hidden.hidden = true;
+        hidden.filtered = true;
+        let seen = item(r#"{"by":"b","id":2,"time":0,"type":"story","title":"y"}"#);
+        cache.write().unwrap().insert(1, hidden);
+        cache.write().unwrap().insert(2, seen);
+
+        HackerNews::save_store(&path, &tops, &cache);
+        let loaded = HackerNews::load_store(&path).expect("store should parse back");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.tops.get(&Feed::Top).unwrap(), &vec![1, 2, 3]);
+        let loaded_hidden = loaded.items.get(&1).unwrap();
+        assert!(loaded_hidden.hidden);
+        assert!(loaded_hidden.filtered);
+        let loaded_seen = loaded.items.get(&2).unwrap();
+        assert!(!loaded_seen.hidden);
+    }
+
+    #[test]
+    fn load_store_returns_none_for_missing_or_corrupt_file() {
+        use super::HackerNews;
+        let path = store_path("missing");
+        let _ = std::fs::remove_file(&path);
+        assert!(HackerNews::load_store(&path).is_none());
+
+        std::fs::write(&path, b"not json").unwrap();
+        assert!(HackerNews::load_store(&path).is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn save_store_does_not_leave_a_dangling_temp_file() {
+        use super::{HackerNews, Feed, TopList, Cache};
+        use std::collections::HashMap;
+        use std::sync::RwLock;
+
+        let path = store_path("atomic");
+        let _ = std::fs::remove_file(&path);
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+
+        let mut tops = HashMap::new();
+        tops.insert(Feed::Top, TopList::default());
+        HackerNews::save_store(&path, &RwLock::new(tops), &Cache::default());
+
+        assert!(path.exists(), "save_store should produce the final file");
+        assert!(!std::path::Path::new(&tmp_path).exists(), "temp file should be renamed away, not left behind");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn dropping_the_last_handle_saves_the_store_without_a_shutdown_call() {
+        use super::HackerNews;
+
+        // The idiomatic "let it go out of scope at the end of main" pattern:
+        // no explicit `shutdown()`, just a bare drop. `hn_thread`'s `Weak`
+        // can no longer upgrade by the time this runs, so the save has to
+        // happen in `Drop` itself rather than being left to the background
+        // thread noticing the shutdown flag.
+        let path = store_path("drop-no-shutdown");
+        let _ = std::fs::remove_file(&path);
+
+        let hn = HackerNews::with_store(&path);
+        drop(hn);
+
+        let loaded = HackerNews::load_store(&path).expect("drop should have written the store");
+        let _ = std::fs::remove_file(&path);
+        assert!(loaded.tops.contains_key(&super::Feed::Top));
+    }
+
+    #[test]
+    fn shutdown_then_drop_saves_the_store_immediately() {
+        use super::HackerNews;
+
+        let path = store_path("drop-after-shutdown");
+        let _ = std::fs::remove_file(&path);
+
+        let hn = HackerNews::with_store(&path);
+        hn.shutdown();
+        drop(hn);
+
+        let loaded = HackerNews::load_store(&path).expect("drop should have written the store");
+        let _ = std::fs::remove_file(&path);
+        assert!(loaded.tops.contains_key(&super::Feed::Top));
+    }
 }